@@ -0,0 +1,76 @@
+//! AccessKit wiring, compiled only with the `accesskit` feature so the
+//! dependency stays optional for users who don't need a screen reader.
+
+use accesskit::{Action, ActionRequest, Node, NodeId, Role, Tree, TreeUpdate};
+use accesskit_winit::Adapter;
+use winit::event_loop::EventLoopProxy;
+use winit::window::Window;
+
+use crate::Event;
+
+const WINDOW_NODE: NodeId = NodeId(0);
+
+/// Bridges egui's accessibility output and AccessKit's action requests to the
+/// native `accesskit_winit::Adapter` for `window`.
+pub struct Accessibility {
+    adapter: Adapter,
+}
+
+impl Accessibility {
+    pub fn new(window: &Window, proxy: EventLoopProxy<Event>) -> Self {
+        let adapter = Adapter::new(window, initial_tree, proxy);
+        Self { adapter }
+    }
+
+    /// Feeds the accessibility tree update produced by `ctx.enable_accesskit()`
+    /// into the adapter. Call this once per frame, after `end_frame`.
+    pub fn update(&mut self, full_output: &egui::FullOutput) {
+        if let Some(update) = full_output.platform_output.accesskit_update.clone() {
+            self.adapter.update_if_active(|| update);
+        }
+    }
+
+    /// Translates an incoming AccessKit action request (focus, default-action)
+    /// into egui input so tabs, menu buttons and the title-bar controls are
+    /// operable via assistive technology.
+    pub fn handle_action_request(&self, ctx: &egui::Context, request: ActionRequest) {
+        // egui derives a widget's AccessKit `NodeId` directly from its `Id`'s raw
+        // value, not by hashing it again, so the request must be mapped back the
+        // same way to land on the widget that produced the node.
+        let id = egui::Id::from_raw(request.target.0);
+
+        match request.action {
+            Action::Focus => {
+                ctx.memory_mut(|memory| memory.request_focus(id));
+            }
+            Action::Default => {
+                ctx.memory_mut(|memory| memory.request_focus(id));
+                // Focusing a widget doesn't click it: synthesize the same
+                // Enter press egui's buttons already treat as activation for a
+                // focused widget so AT "activate" actually fires the click.
+                ctx.input_mut(|input| {
+                    input.events.push(egui::Event::Key {
+                        key: egui::Key::Enter,
+                        physical_key: None,
+                        pressed: true,
+                        repeat: false,
+                        modifiers: egui::Modifiers::NONE,
+                    });
+                });
+                ctx.request_repaint();
+            }
+            _ => {}
+        }
+    }
+}
+
+fn initial_tree() -> TreeUpdate {
+    let mut root = Node::new(Role::Window);
+    root.set_label("egui-wgpu_winit example");
+
+    TreeUpdate {
+        nodes: vec![(WINDOW_NODE, root)],
+        tree: Some(Tree::new(WINDOW_NODE)),
+        focus: WINDOW_NODE,
+    }
+}
@@ -0,0 +1,104 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use egui::text::LayoutJob;
+use egui::{Color32, FontFamily, FontId, TextFormat};
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Style as SynStyle, ThemeSet};
+use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
+
+use crate::Title;
+
+const THEME: &str = "base16-ocean.dark";
+
+/// Builds and caches the per-line [`LayoutJob`]s used to render `TabKind::Source` tabs.
+///
+/// Lines are kept separate (rather than joined into one job) so each one can be
+/// laid out next to its own gutter row and stay vertically aligned with it.
+/// Jobs are keyed by `(title, hash(src))` so re-highlighting only happens when a
+/// buffer's contents actually change, not on every frame.
+pub struct Highlighter {
+    syntax_set: SyntaxSet,
+    theme_set: ThemeSet,
+    cache: HashMap<(Title, u64), Vec<LayoutJob>>,
+}
+
+impl Highlighter {
+    pub fn new() -> Self {
+        Self {
+            // Loaded with line terminators, so lines must be fed to
+            // `highlight_line` via `LinesWithEndings` below rather than
+            // `str::lines()`: syntect's end-of-line-anchored rules (multi-line
+            // comments/strings, preprocessor continuations, ...) only fire
+            // correctly when the `\n` is actually present in the input.
+            syntax_set: SyntaxSet::load_defaults_newlines(),
+            theme_set: ThemeSet::load_defaults(),
+            cache: HashMap::new(),
+        }
+    }
+
+    pub fn highlighted_lines(&mut self, title: Title, src: &str) -> Vec<LayoutJob> {
+        let key = (title, hash_src(src));
+
+        if let Some(lines) = self.cache.get(&key) {
+            return lines.clone();
+        }
+
+        // Drop any stale entry for this tab before inserting the fresh one.
+        self.cache
+            .retain(|(cached_title, _), _| *cached_title != title);
+
+        let lines = self.highlight(title, src);
+        self.cache.insert(key, lines.clone());
+        lines
+    }
+
+    fn highlight(&self, title: Title, src: &str) -> Vec<LayoutJob> {
+        let syntax = self
+            .syntax_set
+            .find_syntax_by_extension(extension_of(title))
+            .unwrap_or_else(|| self.syntax_set.find_syntax_plain_text());
+
+        let theme = &self.theme_set.themes[THEME];
+        let mut highlighter = HighlightLines::new(syntax, theme);
+
+        LinesWithEndings::from(src)
+            .map(|line| {
+                let ranges = highlighter
+                    .highlight_line(line, &self.syntax_set)
+                    .unwrap_or_default();
+
+                let mut job = LayoutJob::default();
+                for (style, text) in ranges {
+                    // The terminator is only ever part of the last range on a
+                    // line; trimming it here (rather than leaving it in the
+                    // line fed to the highlighter) keeps each row's `LayoutJob`
+                    // free of the trailing newline the gutter row would
+                    // otherwise render as blank trailing space.
+                    job.append(text.trim_end_matches(['\n', '\r']), 0.0, text_format(style));
+                }
+                job
+            })
+            .collect()
+    }
+}
+
+fn text_format(style: SynStyle) -> TextFormat {
+    TextFormat {
+        font_id: FontId::new(14.0, FontFamily::Monospace),
+        color: Color32::from_rgb(style.foreground.r, style.foreground.g, style.foreground.b),
+        ..Default::default()
+    }
+}
+
+fn extension_of(title: Title) -> &'static str {
+    title.rsplit('.').next().unwrap_or("txt")
+}
+
+fn hash_src(src: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    src.hash(&mut hasher);
+    hasher.finish()
+}
@@ -1,14 +1,23 @@
 use std::{collections::BTreeMap, time::Instant};
 
-use egui::{Button, CentralPanel, FontFamily, FontId, RichText, TextStyle};
+use egui::{CentralPanel, FontFamily, FontId, RichText, TextStyle};
 use wgpu_backend::{RenderPass, ScreenDescriptor};
 use winit::event::Event::*;
 use winit_backend::{Platform, PlatformDescriptor};
 
 use winit::event_loop::ControlFlow;
 
-mod icons;
+#[cfg(feature = "accesskit")]
+mod accessibility;
+mod highlight;
+mod persistence;
 mod style;
+mod svg_icons;
+
+use highlight::Highlighter;
+use persistence::Session;
+use style::Style;
+use svg_icons::IconCache;
 
 const INITIAL_WIDTH: u32 = 1300;
 const INITIAL_HEIGHT: u32 = 900;
@@ -16,6 +25,8 @@ const INITIAL_HEIGHT: u32 = 900;
 /// A custom event type for the winit app.
 enum Event {
     RequestRedraw,
+    #[cfg(feature = "accesskit")]
+    AccessKitActionRequest(accesskit::ActionRequest),
 }
 
 /// This is the repaint signal type that egui needs for requesting a repaint from another thread.
@@ -28,6 +39,13 @@ impl epi::backend::RepaintSignal for ExampleRepaintSignal {
     }
 }
 
+#[cfg(feature = "accesskit")]
+impl From<accesskit_winit::ActionRequestEvent> for Event {
+    fn from(event: accesskit_winit::ActionRequestEvent) -> Self {
+        Event::AccessKitActionRequest(event.request)
+    }
+}
+
 #[derive(PartialEq)]
 enum TabKind {
     Source(String),
@@ -38,9 +56,19 @@ type Title = &'static str;
 
 struct Buffers {
     inner: BTreeMap<Title, TabKind>,
+    highlighter: Highlighter,
+    style: Style,
 }
 
 impl Buffers {
+    fn new(inner: BTreeMap<Title, TabKind>, style: Style) -> Self {
+        Self {
+            inner,
+            highlighter: Highlighter::new(),
+            style,
+        }
+    }
+
     fn has_multiple_tabs(&self) -> bool {
         self.inner.len() != 1
     }
@@ -51,9 +79,37 @@ impl egui_dock::TabViewer for Buffers {
 
     fn ui(&mut self, ui: &mut egui::Ui, title: &mut Self::Tab) {
         match self.inner.get(title) {
-            Some(TabKind::Source(src)) => ui.label(src),
-            Some(TabKind::Listing(id)) => ui.label(id.to_string()),
-            _ => return,
+            Some(TabKind::Source(src)) => {
+                let src = src.clone();
+                let lines = self.highlighter.highlighted_lines(*title, &src);
+                let gutter_width = lines.len().max(1).to_string().len();
+                let text_color = self.style.text_color;
+
+                self.style.for_scrollbar(ui);
+                egui::ScrollArea::both()
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| {
+                        // Each line is its own row so the gutter number and the
+                        // highlighted code share the same row height and can't drift
+                        // out of alignment the way two independently stacked
+                        // columns would.
+                        for (i, line_job) in lines.into_iter().enumerate() {
+                            ui.horizontal(|ui| {
+                                ui.label(
+                                    RichText::new(format!("{:>gutter_width$}", i + 1))
+                                        .monospace()
+                                        .color(text_color),
+                                );
+                                ui.separator();
+                                ui.label(line_job);
+                            });
+                        }
+                    });
+            }
+            Some(TabKind::Listing(id)) => {
+                ui.label(id.to_string());
+            }
+            None => {}
         };
     }
 
@@ -61,13 +117,13 @@ impl egui_dock::TabViewer for Buffers {
         (*title).into()
     }
 
+    fn closable(&mut self, tab: &mut Self::Tab) -> bool {
+        !matches!(self.inner.get(tab), Some(TabKind::Listing(_)))
+    }
+
     fn on_close(&mut self, tab: &mut Self::Tab) -> bool {
-        if self.inner.len() == 1 {
-            false
-        } else {
-            self.inner.remove(tab);
-            true
-        }
+        self.inner.remove(tab);
+        true
     }
 }
 
@@ -75,7 +131,7 @@ impl egui_dock::TabViewer for Buffers {
 fn main() {
     let event_loop = winit::event_loop::EventLoopBuilder::<Event>::with_user_event().build();
     let window = winit::window::WindowBuilder::new()
-        .with_decorations(true)
+        .with_decorations(false)
         .with_resizable(true)
         .with_transparent(false)
         .with_title("egui-wgpu_winit example")
@@ -86,6 +142,9 @@ fn main() {
         .build(&event_loop)
         .unwrap();
 
+    #[cfg(feature = "accesskit")]
+    let mut accessibility = accessibility::Accessibility::new(&window, event_loop.create_proxy());
+
     let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
         backends: wgpu::Backends::PRIMARY,
         dx12_shader_compiler: wgpu::Dx12Compiler::Fxc,
@@ -135,7 +194,27 @@ fn main() {
 
     surface.configure(&device, &surface_config);
 
-    let style = style::Style::default();
+    let (style, mut tree, buffers_map) = match Session::load(persistence::SESSION_FILE) {
+        Some(session) => session.restore(),
+        None => {
+            let source_title = "Source";
+            let disass_title = "Disassembly";
+
+            let buffers_map = BTreeMap::from([
+                (source_title, TabKind::Listing(1600)),
+                (
+                    disass_title,
+                    TabKind::Source(String::from("line 1\nline 2\nline 3")),
+                ),
+            ]);
+
+            let mut tree = egui_dock::tree::Tree::new(vec![source_title, disass_title]);
+            tree.set_focused_node(egui_dock::NodeIndex::root());
+
+            (Style::default(), tree, buffers_map)
+        }
+    };
+
     let dock_style = style.dock();
 
     let mut egui_style = style.egui();
@@ -171,22 +250,9 @@ fn main() {
     // We use the egui_wgpu_backend crate as the render backend
     let mut egui_rpass = RenderPass::new(&device, surface_format, 1);
 
-    let source_title = icon!(EMBED2, "Source");
-    let disass_title = icon!(PARAGRAPH_LEFT, "Disassembly");
-
-    let buffers = BTreeMap::from([
-        (source_title, TabKind::Listing(1600)),
-        (
-            disass_title,
-            TabKind::Source(String::from("line 1\nline 2\nline 3")),
-        ),
-    ]);
-
-    let mut buffers = Buffers { inner: buffers };
-
-    // init tab tree
-    let mut tree = egui_dock::tree::Tree::new(vec![source_title, disass_title]);
-    tree.set_focused_node(egui_dock::NodeIndex::root());
+    let mut buffers = Buffers::new(buffers_map, style.clone());
+    let mut allowed_splits = egui_dock::AllowedSplits::All;
+    let mut icon_cache = IconCache::new();
 
     let start_time = Instant::now();
     event_loop.run(move |event, _, control_flow| {
@@ -212,6 +278,13 @@ fn main() {
                 // Begin to draw the UI frame
                 platform.begin_frame();
 
+                // Tells egui to record an AccessKit tree while laying out this
+                // frame's widgets; without this `accesskit_update` below is
+                // always `None` and the adapter never sees anything past the
+                // static root node.
+                #[cfg(feature = "accesskit")]
+                platform.context().enable_accesskit();
+
                 // Draw the primary panel
                 CentralPanel::default()
                     .frame(
@@ -243,17 +316,31 @@ fn main() {
                             }
                         }
 
-                        title_bar_ui(ui, &mut platform);
+                        title_bar_ui(
+                            ui,
+                            &window,
+                            control_flow,
+                            &tree,
+                            &buffers.inner,
+                            &mut allowed_splits,
+                            &mut icon_cache,
+                            &style,
+                        );
 
                         egui_dock::DockArea::new(&mut tree)
                             .style(dock_style.clone())
                             .show_close_buttons(buffers.has_multiple_tabs())
                             .draggable_tabs(buffers.has_multiple_tabs())
+                            .allowed_splits(allowed_splits)
                             .show_inside(ui, &mut buffers);
                     });
 
                 // end the UI frame. We could now handle the output and draw the UI with the backend
                 let full_output = platform.end_frame(Some(&window));
+
+                #[cfg(feature = "accesskit")]
+                accessibility.update(&full_output);
+
                 let paint_jobs = platform.context().tessellate(full_output.shapes);
 
                 let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
@@ -298,6 +385,10 @@ fn main() {
             MainEventsCleared | UserEvent(Event::RequestRedraw) => {
                 window.request_redraw();
             }
+            #[cfg(feature = "accesskit")]
+            UserEvent(Event::AccessKitActionRequest(request)) => {
+                accessibility.handle_action_request(&platform.context(), request);
+            }
             WindowEvent { event, .. } => match event {
                 winit::event::WindowEvent::Resized(size) => {
                     if size.width > 0 && size.height > 0 {
@@ -307,6 +398,7 @@ fn main() {
                     }
                 }
                 winit::event::WindowEvent::CloseRequested => {
+                    save_session(&style, &tree, &buffers.inner);
                     *control_flow = ControlFlow::Exit;
                 }
                 _ => {}
@@ -316,12 +408,47 @@ fn main() {
     });
 }
 
-fn title_bar_ui(ui: &mut egui::Ui, platform: &mut Platform) {
+/// Captures and saves the session, logging (not panicking) on failure so a
+/// full disk or unwritable cwd doesn't stop the window from closing.
+fn save_session(style: &Style, tree: &egui_dock::Tree<Title>, buffers: &BTreeMap<Title, TabKind>) {
+    let session = Session::capture(style, tree, buffers);
+    if let Err(err) = session.save(persistence::SESSION_FILE) {
+        eprintln!("Failed to save session: {}", err);
+    }
+}
+
+fn title_bar_ui(
+    ui: &mut egui::Ui,
+    window: &winit::window::Window,
+    control_flow: &mut ControlFlow,
+    tree: &egui_dock::Tree<Title>,
+    buffers: &BTreeMap<Title, TabKind>,
+    allowed_splits: &mut egui_dock::AllowedSplits,
+    icon_cache: &mut IconCache,
+    style: &Style,
+) {
+    // Sense drag over the whole bar area *before* the menu/button widgets are
+    // added below. egui resolves overlapping hit-tests in add order (later
+    // wins), so widgets added afterward naturally take click priority over
+    // this background sense and only the empty region between them ever
+    // starts a window drag.
+    let height = ui.spacing().interact_size.y + 2.0 * ui.spacing().button_padding.y;
+    let drag_rect =
+        egui::Rect::from_min_size(ui.cursor().min, egui::vec2(ui.available_width(), height));
+    let drag_response = ui.interact(
+        drag_rect,
+        ui.id().with("title_bar_drag"),
+        egui::Sense::drag(),
+    );
+
     egui::menu::bar(ui, |ui| {
         ui.menu_button("File", |ui| {
-            if ui.button(icon!(FOLDER_OPEN, "open")).clicked() {
-                ui.close_menu();
-            }
+            ui.horizontal(|ui| {
+                icon_cache.svg_button(ui, "folder_open", 14.0, style.text_color);
+                if ui.button("Open").clicked() {
+                    ui.close_menu();
+                }
+            });
         });
 
         ui.menu_button("Edit", |ui| {
@@ -330,43 +457,70 @@ fn title_bar_ui(ui: &mut egui::Ui, platform: &mut Platform) {
                     ui.close_menu();
                 }
             });
+
+            ui.menu_button("Allowed splits", |ui| {
+                ui.radio_value(allowed_splits, egui_dock::AllowedSplits::All, "All");
+                ui.radio_value(
+                    allowed_splits,
+                    egui_dock::AllowedSplits::LeftRightOnly,
+                    "Left/right only",
+                );
+                ui.radio_value(
+                    allowed_splits,
+                    egui_dock::AllowedSplits::TopBottomOnly,
+                    "Top/bottom only",
+                );
+                ui.radio_value(allowed_splits, egui_dock::AllowedSplits::None, "None");
+            });
         });
 
         ui.with_layout(egui::Layout::right_to_left(egui::Align::Max), |ui| {
             ui.spacing_mut().item_spacing.x = 0.0;
-            close_maximize_minimize(ui, platform);
+            close_maximize_minimize(ui, window, control_flow, tree, buffers, icon_cache, style);
         });
     });
+
+    if drag_response.drag_started() {
+        window.drag_window().ok();
+    }
 }
 
 // Show some close/maximize/minimize buttons for the native window.
-fn close_maximize_minimize(ui: &mut egui::Ui, platform: &mut Platform) {
+fn close_maximize_minimize(
+    ui: &mut egui::Ui,
+    window: &winit::window::Window,
+    control_flow: &mut ControlFlow,
+    tree: &egui_dock::Tree<Title>,
+    buffers: &BTreeMap<Title, TabKind>,
+    icon_cache: &mut IconCache,
+    style: &Style,
+) {
     let height = 12.0;
-    let close_response = ui.add(Button::new(RichText::new(icon!(CROSS, "")).size(height)));
+    let close_response = icon_cache.svg_button(ui, "cross", height, style.text_color);
 
     if close_response.clicked() {
-        // platform.close();
+        // The window has no native decorations, so this button is the common
+        // way users close it; go through the same save path as a native
+        // `WindowEvent::CloseRequested` instead of just exiting silently.
+        save_session(style, tree, buffers);
+        *control_flow = ControlFlow::Exit;
     }
 
-    // if platform.window_info.maximized {
-    //     let maximized_response = ui
-    //         .add(Button::new(RichText::new("ðŸ——").size(button_height)));
-
-    //     if maximized_response.clicked() {
-    //         // platform.set_maximized(false);
-    //     }
-    // } else {
-    let maximized_response = ui.add(Button::new(
-        RichText::new(icon!(CHECKBOX_UNCHECKED, "")).size(height),
-    ));
+    let maximized = window.is_maximized();
+    let maximize_icon = if maximized {
+        "window_restore"
+    } else {
+        "checkbox_unchecked"
+    };
+    let maximized_response = icon_cache.svg_button(ui, maximize_icon, height, style.text_color);
 
     if maximized_response.clicked() {
-        // platform.set_maximized(true);
+        window.set_maximized(!maximized);
     }
 
-    let minimized_response = ui.add(Button::new(RichText::new(icon!(MINUS, "")).size(height)));
+    let minimized_response = icon_cache.svg_button(ui, "minus", height, style.text_color);
 
     if minimized_response.clicked() {
-        // platform.set_minimized(true);
+        window.set_minimized(true);
     }
 }
@@ -0,0 +1,172 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use egui_dock::{Node, NodeIndex, Tree};
+use serde::{Deserialize, Serialize};
+
+use crate::{style::Style, TabKind, Title};
+
+/// Where the session (dock layout, open tabs, theme) is persisted between runs.
+pub const SESSION_FILE: &str = "session.json";
+
+#[derive(Serialize, Deserialize)]
+struct SerializedTab {
+    title: String,
+    kind: SerializedTabKind,
+}
+
+#[derive(Serialize, Deserialize)]
+enum SerializedTabKind {
+    Source(String),
+    Listing(usize),
+}
+
+/// One slot of `Tree`'s node array, keyed positionally: slot `i`'s children
+/// (if any) live at `2i + 1` and `2i + 2`, mirroring `egui_dock`'s own layout.
+/// Capturing every slot (not just the leaves) is what lets `restore` rebuild
+/// the actual split geometry instead of flattening everything into one tab
+/// group.
+#[derive(Serialize, Deserialize)]
+enum SerializedNode {
+    Empty,
+    Leaf { tabs: Vec<String>, active: usize },
+    Horizontal { fraction: f32 },
+    Vertical { fraction: f32 },
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Session {
+    style: Style,
+    tabs: Vec<SerializedTab>,
+    nodes: Vec<SerializedNode>,
+    focused: Option<usize>,
+}
+
+impl Session {
+    pub fn capture(style: &Style, tree: &Tree<Title>, buffers: &BTreeMap<Title, TabKind>) -> Self {
+        let tabs = buffers
+            .iter()
+            .map(|(title, kind)| SerializedTab {
+                title: title.to_string(),
+                kind: match kind {
+                    TabKind::Source(src) => SerializedTabKind::Source(src.clone()),
+                    TabKind::Listing(id) => SerializedTabKind::Listing(*id),
+                },
+            })
+            .collect();
+
+        let nodes = tree
+            .iter()
+            .map(|node| match node {
+                Node::Empty => SerializedNode::Empty,
+                Node::Leaf { tabs, active, .. } => SerializedNode::Leaf {
+                    tabs: tabs.iter().map(|title| title.to_string()).collect(),
+                    active: active.0,
+                },
+                Node::Horizontal { fraction, .. } => SerializedNode::Horizontal {
+                    fraction: *fraction,
+                },
+                Node::Vertical { fraction, .. } => SerializedNode::Vertical {
+                    fraction: *fraction,
+                },
+            })
+            .collect();
+
+        Self {
+            style: style.clone(),
+            tabs,
+            nodes,
+            focused: tree.focused_leaf().map(|idx| idx.0),
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let json = serde_json::to_string_pretty(self).expect("session always serializes");
+        fs::write(path, json)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Option<Self> {
+        let json = fs::read_to_string(path).ok()?;
+        serde_json::from_str(&json).ok()
+    }
+
+    /// Rebuilds the tab contents, dock layout and theme saved in this session.
+    ///
+    /// The tree is replayed node-by-node with `split_right`/`split_below`
+    /// (the same calls a user's interactive drag-split would make), so
+    /// left/right and top/bottom splits come back exactly as they were,
+    /// rather than being collapsed into a single tab group.
+    pub fn restore(self) -> (Style, Tree<Title>, BTreeMap<Title, TabKind>) {
+        let mut buffers = BTreeMap::new();
+        for tab in self.tabs {
+            let title: Title = Box::leak(tab.title.into_boxed_str());
+            let kind = match tab.kind {
+                SerializedTabKind::Source(src) => TabKind::Source(src),
+                SerializedTabKind::Listing(id) => TabKind::Listing(id),
+            };
+            buffers.insert(title, kind);
+        }
+
+        let root_tabs = leaf_tabs(&self.nodes, NodeIndex::root(), &buffers);
+        let mut tree = Tree::new(root_tabs);
+        rebuild(&mut tree, NodeIndex::root(), &self.nodes, &buffers);
+
+        if let Some(focused) = self.focused {
+            tree.set_focused_node(NodeIndex(focused));
+        }
+
+        (self.style, tree, buffers)
+    }
+}
+
+/// Finds the tabs of the leaf that currently occupies `idx`'s slot before any
+/// further splitting happens there. A split always relocates its parent's
+/// existing tabs wholly onto the left/top child, so for an internal node this
+/// recurses into that child rather than `idx` itself.
+fn leaf_tabs(
+    nodes: &[SerializedNode],
+    idx: NodeIndex,
+    buffers: &BTreeMap<Title, TabKind>,
+) -> Vec<Title> {
+    match nodes.get(idx.0) {
+        Some(SerializedNode::Leaf { tabs, .. }) => tabs
+            .iter()
+            .filter_map(|title| buffers.keys().find(|key| ***key == *title).copied())
+            .collect(),
+        Some(SerializedNode::Horizontal { .. }) | Some(SerializedNode::Vertical { .. }) => {
+            leaf_tabs(nodes, NodeIndex(idx.0 * 2 + 1), buffers)
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Replays the splits recorded in `nodes`, starting from `idx` (already
+/// present in `tree` as a leaf seeded via [`leaf_tabs`]).
+fn rebuild(
+    tree: &mut Tree<Title>,
+    idx: NodeIndex,
+    nodes: &[SerializedNode],
+    buffers: &BTreeMap<Title, TabKind>,
+) {
+    match nodes.get(idx.0) {
+        Some(SerializedNode::Leaf { active, tabs }) => {
+            if let Node::Leaf { active: a, .. } = &mut tree[idx] {
+                *a = egui_dock::TabIndex((*active).min(tabs.len().saturating_sub(1)));
+            }
+        }
+        Some(SerializedNode::Horizontal { fraction }) => {
+            let right = leaf_tabs(nodes, NodeIndex(idx.0 * 2 + 2), buffers);
+            tree.split_right(idx, *fraction, right);
+            rebuild(tree, NodeIndex(idx.0 * 2 + 1), nodes, buffers);
+            rebuild(tree, NodeIndex(idx.0 * 2 + 2), nodes, buffers);
+        }
+        Some(SerializedNode::Vertical { fraction }) => {
+            let below = leaf_tabs(nodes, NodeIndex(idx.0 * 2 + 2), buffers);
+            tree.split_below(idx, *fraction, below);
+            rebuild(tree, NodeIndex(idx.0 * 2 + 1), nodes, buffers);
+            rebuild(tree, NodeIndex(idx.0 * 2 + 2), nodes, buffers);
+        }
+        Some(SerializedNode::Empty) | None => {}
+    }
+}
@@ -1,6 +1,10 @@
-use egui::{Rounding, Color32, Stroke, Ui};
+use std::fs;
+use std::path::Path;
 
-#[derive(Clone)]
+use egui::{Color32, Rounding, Stroke, Ui};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Style {
     pub separator_width: f32,
     pub active_background: Color32,
@@ -32,6 +36,17 @@ impl Default for Style {
 }
 
 impl Style {
+    /// Loads a previously [`save`](Style::save)d theme from `path`, if present and valid.
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        let json = fs::read_to_string(path).ok()?;
+        serde_json::from_str(&json).ok()
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let json = serde_json::to_string_pretty(self).expect("style always serializes");
+        fs::write(path, json)
+    }
+
     pub fn dock(&self) -> egui_dock::Style {
         egui_dock::Style {
             dock_area_padding: None,
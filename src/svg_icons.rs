@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+
+use egui::{
+    Color32, ColorImage, Context, ImageButton, Response, TextureHandle, TextureOptions, Ui, Vec2,
+};
+
+/// Icons are rasterized at `pixels_per_point * OVERSAMPLE` so they stay crisp
+/// even when the user zooms in past 100%.
+const OVERSAMPLE: f32 = 2.0;
+
+type CacheKey = (&'static str, u32);
+
+/// Rasterizes the bundled SVG icons and caches the resulting textures.
+///
+/// Textures are keyed by `(name, physical_size)` and re-rasterized whenever
+/// `ctx.pixels_per_point()` changes, so icons stay sharp after a monitor or
+/// scale-factor change instead of being scaled up from a stale bitmap.
+#[derive(Default)]
+pub struct IconCache {
+    textures: HashMap<CacheKey, (f32, TextureHandle)>,
+}
+
+impl IconCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn texture(&mut self, ctx: &Context, name: &'static str, size: f32) -> TextureHandle {
+        let pixels_per_point = ctx.pixels_per_point();
+        let physical_size = (size * pixels_per_point * OVERSAMPLE).round() as u32;
+        let key = (name, physical_size);
+
+        if let Some((cached_ppp, texture)) = self.textures.get(&key) {
+            if *cached_ppp == pixels_per_point {
+                return texture.clone();
+            }
+        }
+
+        let image = rasterize(name, physical_size);
+        let texture = ctx.load_texture(name, image, TextureOptions::LINEAR);
+        self.textures
+            .insert(key, (pixels_per_point, texture.clone()));
+        texture
+    }
+
+    /// Draws `name` as a square button of `size` points, tinted with `color`.
+    pub fn svg_button(
+        &mut self,
+        ui: &mut Ui,
+        name: &'static str,
+        size: f32,
+        color: Color32,
+    ) -> Response {
+        let texture = self.texture(ui.ctx(), name, size);
+        ui.add(ImageButton::new(&texture, Vec2::splat(size)).tint(color))
+    }
+}
+
+fn rasterize(name: &'static str, size: u32) -> ColorImage {
+    let size = size.max(1);
+    let tree = usvg::Tree::from_data(source_of(name), &usvg::Options::default().to_ref())
+        .expect("bundled icon svgs are always valid");
+
+    let mut pixmap = tiny_skia::Pixmap::new(size, size).expect("icon size is never zero");
+    let scale = size as f32 / tree.size.width().max(tree.size.height());
+
+    resvg::render(
+        &tree,
+        usvg::FitTo::Original,
+        tiny_skia::Transform::from_scale(scale, scale),
+        pixmap.as_mut(),
+    );
+
+    ColorImage::from_rgba_unmultiplied([size as usize, size as usize], pixmap.data())
+}
+
+fn source_of(name: &'static str) -> &'static [u8] {
+    match name {
+        "embed2" => include_bytes!("../assets/icons/embed2.svg"),
+        "paragraph_left" => include_bytes!("../assets/icons/paragraph_left.svg"),
+        "folder_open" => include_bytes!("../assets/icons/folder_open.svg"),
+        "cross" => include_bytes!("../assets/icons/cross.svg"),
+        "checkbox_unchecked" => include_bytes!("../assets/icons/checkbox_unchecked.svg"),
+        "window_restore" => include_bytes!("../assets/icons/window_restore.svg"),
+        "minus" => include_bytes!("../assets/icons/minus.svg"),
+        _ => panic!("no bundled svg icon named {name:?}"),
+    }
+}